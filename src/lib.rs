@@ -2,23 +2,174 @@
 extern crate expectest;
 pub use expectest::prelude::*;
 
+use std::any::Any;
+use std::cell::RefCell;
+use std::fmt;
+use std::marker::PhantomData;
+use std::mem;
+use std::ptr;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
 pub type TestResult = Result<(), ()>;
 
 pub struct Context<'a> {
-    tests: Vec<Box<FnMut() -> TestResult + 'a>>,
-    before_each: Vec<Box<FnMut() -> () + 'a>>
+    name: String,
+    tests: Vec<(String, Box<FnMut() -> TestResult + 'a>)>,
+    benches: Vec<(String, Box<FnMut(&mut Bencher) -> () + 'a>)>,
+    children: Vec<Context<'a>>,
+    before_each: Vec<Box<FnMut() -> () + 'a>>,
+    after_each: Vec<Box<FnMut() -> () + 'a>>,
+    env: Rc<RefCell<Environment>>
+}
+
+struct Slot {
+    init: Box<dyn Fn() -> Box<dyn Any>>,
+    value: Option<Box<dyn Any>>
+}
+
+// Backs `ctx.set`: a store of lazily-evaluated, memoized values, keyed
+// by the index each `set` call was registered under, shared by every `Context` node in a tree (root and all
+// `describe` children alike) and reset before each example so a `let`
+// never leaks into the next one.
+struct Environment {
+    slots: Vec<Slot>
+}
+
+impl Environment {
+    fn new() -> Environment {
+        Environment { slots: vec!() }
+    }
+
+    fn register<T, F>(&mut self, init: F) -> usize
+        where T: 'static,
+              F: 'static + Fn() -> T {
+
+        self.slots.push(Slot { init: Box::new(move || Box::new(Rc::new(init()))), value: None });
+        self.slots.len() - 1
+    }
+
+    fn resolve<T: 'static>(&mut self, id: usize) -> Rc<T> {
+        let slot = &mut self.slots[id];
+        if slot.value.is_none() {
+            slot.value = Some((slot.init)());
+        }
+        slot.value.as_ref().unwrap().downcast_ref::<Rc<T>>().unwrap().clone()
+    }
+
+    fn reset(&mut self) {
+        for slot in self.slots.iter_mut() {
+            slot.value = None;
+        }
+    }
+}
+
+/// A cheap, cloneable handle to a value that's lazily computed and
+/// memoized on first `get()`, then reset before the next example.
+pub struct Let<T> {
+    id: usize,
+    env: Rc<RefCell<Environment>>,
+    _marker: PhantomData<T>
+}
+
+impl<T> Clone for Let<T> {
+    fn clone(&self) -> Let<T> {
+        Let { id: self.id, env: self.env.clone(), _marker: PhantomData }
+    }
+}
+
+impl<T: 'static> Let<T> {
+    pub fn get(&self) -> Rc<T> {
+        self.env.borrow_mut().resolve(self.id)
+    }
+}
+
+// Turns a generated case's value (by its `{:?}` representation) into
+// something safe to splice into an example name: anything that isn't
+// alphanumeric or `_` becomes `_`.
+fn sanitize_identifier(repr: &str) -> String {
+    repr.chars()
+        .map(|c| if c.is_alphanumeric() || c == '_' { c } else { '_' })
+        .collect()
 }
 
 impl<'a> Context<'a> {
-    pub fn describe<F>(&mut self, _name: &'a str, mut body: F)
+    fn new(name: &str) -> Context<'a> {
+        Context::with_env(name, Rc::new(RefCell::new(Environment::new())))
+    }
+
+    fn with_env(name: &str, env: Rc<RefCell<Environment>>) -> Context<'a> {
+        Context { name: name.to_string(), tests: vec!(), benches: vec!(), children: vec!(), before_each: vec!(), after_each: vec!(), env: env }
+    }
+
+    pub fn describe<F>(&mut self, name: &str, mut body: F)
         where F : 'a + FnMut(&mut Context<'a>) -> () {
-        body(self)
+
+        let mut child = Context::with_env(name, self.env.clone());
+        body(&mut child);
+        self.children.push(child)
     }
 
-    pub fn it<F>(&mut self, _name: &'a str, body: F)
+    pub fn it<F>(&mut self, name: &str, body: F)
         where F : 'a + FnMut() -> TestResult {
 
-        self.tests.push(Box::new(body))
+        self.tests.push((name.to_string(), Box::new(body)))
+    }
+
+    /// Expands into one example per value in `values`, named
+    /// `"<name>_<index>_<value>"`.
+    pub fn it_each<T, I, F>(&mut self, name: &str, values: I, body: F)
+        where T: fmt::Debug + Clone + 'a,
+              I: IntoIterator<Item = T>,
+              F: 'a + FnMut(T) -> TestResult {
+
+        let shared_body = Rc::new(RefCell::new(body));
+
+        for (index, value) in values.into_iter().enumerate() {
+            let case_name = format!("{}_{}_{}", name, index, sanitize_identifier(&format!("{:?}", value)));
+            let shared_body = shared_body.clone();
+
+            self.tests.push((case_name, Box::new(move || {
+                (shared_body.borrow_mut())(value.clone())
+            })));
+        }
+    }
+
+    /// The cartesian product of `it_each`: one example per pair drawn
+    /// from `a_values` and `b_values`.
+    pub fn it_matrix<A, B, F>(&mut self, name: &str, a_values: impl IntoIterator<Item = A>,
+                               b_values: impl IntoIterator<Item = B>, body: F)
+        where A: fmt::Debug + Clone + 'a,
+              B: fmt::Debug + Clone + 'a,
+              F: 'a + FnMut(A, B) -> TestResult {
+
+        let a_values: Vec<A> = a_values.into_iter().collect();
+        let b_values: Vec<B> = b_values.into_iter().collect();
+        let shared_body = Rc::new(RefCell::new(body));
+
+        let mut index = 0;
+        for a in a_values.iter() {
+            for b in b_values.iter() {
+                let case_name = format!("{}_{}_{}_{}", name, index,
+                    sanitize_identifier(&format!("{:?}", a)),
+                    sanitize_identifier(&format!("{:?}", b)));
+                let shared_body = shared_body.clone();
+                let a = a.clone();
+                let b = b.clone();
+
+                self.tests.push((case_name, Box::new(move || {
+                    (shared_body.borrow_mut())(a.clone(), b.clone())
+                })));
+
+                index += 1;
+            }
+        }
+    }
+
+    pub fn bench<F>(&mut self, name: &str, body: F)
+        where F : 'a + FnMut(&mut Bencher) -> () {
+
+        self.benches.push((name.to_string(), Box::new(body)))
     }
 
     pub fn before<F>(&mut self, body: F)
@@ -26,72 +177,452 @@ impl<'a> Context<'a> {
 
         self.before_each.push(Box::new(body))
     }
+
+    pub fn after<F>(&mut self, body: F)
+        where F : 'a + FnMut() -> () {
+
+        self.after_each.push(Box::new(body))
+    }
+
+    /// Registers a memoized shared subject: `init` runs at most once per
+    /// example, the first time the returned `Let` is dereferenced.
+    pub fn set<T, F>(&mut self, init: F) -> Let<T>
+        where T: 'static,
+              F: 'static + Fn() -> T {
+
+        let id = self.env.borrow_mut().register(init);
+        Let { id: id, env: self.env.clone(), _marker: PhantomData }
+    }
 }
 
 
-pub fn describe<'a, 'b, F>(_block_name: &'b str, body: F) -> Runner<'a>
+pub fn describe<'a, 'b, F>(block_name: &'b str, body: F) -> Runner<'a>
     where F : 'a + FnOnce(&mut Context<'a>) -> () {
 
-    let mut c = Context { tests: vec!(), before_each: vec!() };
+    let mut c = Context::new(block_name);
     body(&mut c);
-    Runner { describe: c, report: None }
+    Runner { describe: c, report: None, config: RunConfig::default() }
+}
+
+/// Callbacks driven by `Runner` as it walks the `Context` tree, so a test
+/// run can be observed (printed, recorded, streamed to CI, ...) without
+/// `Runner` knowing anything about the output format.
+pub trait Reporter {
+    fn enter_describe(&mut self, _name: &str) {}
+    fn exit_describe(&mut self) {}
+    fn start_example(&mut self, _name: &str) {}
+    fn end_example(&mut self, _name: &str, _result: TestResult, _duration: Duration) {}
+}
+
+struct NullReporter;
+impl Reporter for NullReporter {}
+
+/// Prints the nested `describe`/`it` tree as it runs, indented one level
+/// per `describe`, with a pass/fail marker on each example.
+pub struct DocumentationReporter {
+    depth: usize
+}
+
+impl DocumentationReporter {
+    pub fn new() -> DocumentationReporter {
+        DocumentationReporter { depth: 0 }
+    }
+
+    fn indent(&self) -> String {
+        "  ".repeat(self.depth)
+    }
+}
+
+impl Reporter for DocumentationReporter {
+    fn enter_describe(&mut self, name: &str) {
+        println!("{}{}", self.indent(), name);
+        self.depth += 1;
+    }
+
+    fn exit_describe(&mut self) {
+        self.depth -= 1;
+    }
+
+    fn end_example(&mut self, name: &str, result: TestResult, duration: Duration) {
+        let marker = if result.is_ok() { "✓" } else { "✗" };
+        println!("{}{} {} ({:?})", self.indent(), marker, name, duration);
+    }
+}
+
+/// Records each example's full `describe::describe::it` path, outcome and
+/// timing as a JSON array, so CI tooling can consume a run's results.
+pub struct JsonReporter {
+    path: Vec<String>,
+    examples: Vec<String>
+}
+
+impl JsonReporter {
+    pub fn new() -> JsonReporter {
+        JsonReporter { path: vec!(), examples: vec!() }
+    }
+
+    pub fn to_json(&self) -> String {
+        format!("[{}]", self.examples.join(","))
+    }
+}
+
+fn escape_json(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c)
+        }
+    }
+    escaped
+}
+
+impl Reporter for JsonReporter {
+    fn enter_describe(&mut self, name: &str) {
+        self.path.push(name.to_string());
+    }
+
+    fn exit_describe(&mut self) {
+        self.path.pop();
+    }
+
+    fn end_example(&mut self, name: &str, result: TestResult, duration: Duration) {
+        let mut full_path = self.path.clone();
+        full_path.push(name.to_string());
+        let outcome = if result.is_ok() { "passed" } else { "failed" };
+
+        self.examples.push(format!(
+            "{{\"path\":\"{}\",\"outcome\":\"{}\",\"duration_ns\":{}}}",
+            escape_json(&full_path.join("::")),
+            outcome,
+            duration.as_nanos()
+        ));
+    }
+}
+
+/// A `filter` substring (or, with `exact`, a full match) keeps only the
+/// examples whose `describe::describe::it` path matches, and `invert`
+/// flips that selection to skip them instead.
+#[derive(Debug, Clone, Default)]
+pub struct RunConfig {
+    filter: Option<String>,
+    exact: bool,
+    invert: bool
+}
+
+impl RunConfig {
+    pub fn new() -> RunConfig {
+        RunConfig::default()
+    }
+
+    pub fn filter<S: Into<String>>(mut self, filter: S) -> RunConfig {
+        self.filter = Some(filter.into());
+        self
+    }
+
+    pub fn exact(mut self, exact: bool) -> RunConfig {
+        self.exact = exact;
+        self
+    }
+
+    pub fn invert(mut self, invert: bool) -> RunConfig {
+        self.invert = invert;
+        self
+    }
+
+    fn matches(&self, full_path: &str) -> bool {
+        let matched = match self.filter {
+            Some(ref filter) => if self.exact {
+                full_path == filter.as_str()
+            } else {
+                full_path.contains(filter.as_str())
+            },
+            None => true
+        };
+
+        if self.invert { !matched } else { matched }
+    }
 }
 
 pub struct Runner<'a> {
     describe: Context<'a>,
-    report: Option<Result<TestReport, TestReport>>
+    report: Option<Result<TestReport, TestReport>>,
+    config: RunConfig
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[derive(Debug, PartialEq, Clone, Default)]
 pub struct TestReport {
     total_tests: u32,
     success_count: u32,
-    error_count: u32
+    error_count: u32,
+    filtered_count: u32,
+    benches: Vec<BenchReport>
 }
 
-impl<'a> Runner<'a> {
+impl TestReport {
+    pub fn total_tests(&self) -> u32 { self.total_tests }
+    pub fn success_count(&self) -> u32 { self.success_count }
+    pub fn error_count(&self) -> u32 { self.error_count }
+    pub fn filtered_count(&self) -> u32 { self.filtered_count }
+    pub fn benches(&self) -> &[BenchReport] { &self.benches }
+}
 
-    pub fn run(&mut self) -> Result<(), ()> {
-        use std::panic::{catch_unwind, AssertUnwindSafe};
+/// A single `ctx.bench` result: the median cost per iteration and the
+/// standard deviation of the trimmed samples around their mean, in
+/// nanoseconds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchReport {
+    pub path: String,
+    pub ns_per_iter: u64,
+    pub deviation_ns: u64
+}
 
-        let mut report = TestReport::default();
-        let mut result = Ok(());
+#[derive(Debug, Clone, Copy)]
+struct BenchSample {
+    ns_per_iter: u64,
+    deviation_ns: u64
+}
 
-        let ref mut describe = self.describe;
-        let ref mut before_functions = describe.before_each;
-        for test_function in describe.tests.iter_mut() {
+/// Passed to a `ctx.bench` body to time a piece of work: a short warmup
+/// estimates the per-call cost, then a batch of timed samples is
+/// collected, the fastest/slowest 10% are trimmed as outliers, and the
+/// median of what remains is reported.
+pub struct Bencher {
+    sample: Option<BenchSample>
+}
 
-            let res = catch_unwind(AssertUnwindSafe(|| {
-                for before_function in before_functions.iter_mut() {
-                    before_function()
-                }
-                test_function()
-            }));
+impl Bencher {
+    fn new() -> Bencher {
+        Bencher { sample: None }
+    }
+
+    pub fn iter<T, F: FnMut() -> T>(&mut self, mut inner: F) {
+        let warmup_budget = Duration::from_millis(10);
+        let warmup_start = Instant::now();
+        let mut warmup_iters: u64 = 0;
+        while warmup_start.elapsed() < warmup_budget {
+            black_box(inner());
+            warmup_iters += 1;
+        }
+
+        // Size each sample to roughly 1ms of work from the warmup estimate.
+        let estimated_ns = (warmup_start.elapsed().as_nanos() as u64 / warmup_iters.max(1)).max(1);
+        let iters_per_sample = (1_000_000 / estimated_ns).max(1) as u32;
+
+        let sample_size = 50;
+        let mut samples = Vec::with_capacity(sample_size);
+        for _ in 0..sample_size {
+            let started_at = Instant::now();
+            for _ in 0..iters_per_sample {
+                black_box(inner());
+            }
+            samples.push(started_at.elapsed() / iters_per_sample);
+        }
+
+        samples.sort();
+
+        let trim = samples.len() / 10;
+        let trimmed = &samples[trim..samples.len() - trim];
+
+        let ns_per_iter = trimmed[trimmed.len() / 2].as_nanos() as u64;
+        let mean_ns = trimmed.iter().map(|d| d.as_nanos() as f64).sum::<f64>() / trimmed.len() as f64;
+        let variance = trimmed.iter()
+            .map(|d| { let diff = d.as_nanos() as f64 - mean_ns; diff * diff })
+            .sum::<f64>() / trimmed.len() as f64;
+
+        self.sample = Some(BenchSample { ns_per_iter: ns_per_iter, deviation_ns: variance.sqrt() as u64 });
+    }
 
-            let res = match res {
-                Ok(res) => res,
-                _ => Err(())
-            };
+    fn take_sample(&mut self) -> Option<BenchSample> {
+        self.sample.take()
+    }
+}
+
+/// An identity function the optimizer can't see through, so the work a
+/// benchmark does isn't eliminated as dead code: a volatile read forces
+/// the value to actually be produced.
+pub fn black_box<T>(dummy: T) -> T {
+    unsafe {
+        let ret = ptr::read_volatile(&dummy);
+        mem::forget(dummy);
+        ret
+    }
+}
+
+// Bundles the state threaded through the whole `run_context` walk (as
+// opposed to `context`, which is the tree node currently being visited),
+// so a new feature doesn't mean another positional parameter.
+struct RunState<'b, 'r, 'a: 'b> {
+    befores: Vec<&'b mut (FnMut() -> () + 'a)>,
+    afters: Vec<&'b mut (FnMut() -> () + 'a)>,
+    path: Vec<String>,
+    report: TestReport,
+    reporter: &'r mut dyn Reporter,
+    config: &'r RunConfig
+}
+
+// Walks `context` and its descendants depth-first, accumulating the
+// ancestor `before_each`/`after_each` hooks on `befores`/`afters` as it
+// goes down and popping them back off before returning, so every `it`
+// sees exactly its own chain of ancestor hooks: before hooks fire
+// outermost-first, after hooks fire innermost-first, and the after hooks
+// run even if the test body panics.
+fn run_context<'a, 'b, 'r>(context: &'b mut Context<'a>,
+                            state: &mut RunState<'b, 'r, 'a>,
+                            result: Result<(), ()>) -> Result<(), ()> {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    let mut result = result;
+
+    let Context { ref name, ref mut tests, ref mut benches, ref mut children, ref mut before_each, ref mut after_each, ref env } = *context;
+    let before_count = before_each.len();
+    let after_count = after_each.len();
+
+    state.reporter.enter_describe(name);
+    state.path.push(name.clone());
+
+    for hook in before_each.iter_mut() {
+        state.befores.push(&mut **hook);
+    }
+    for hook in after_each.iter_mut() {
+        state.afters.push(&mut **hook);
+    }
+
+    for &mut (ref name, ref mut test_function) in tests.iter_mut() {
+        let full_path = {
+            let mut parts = state.path.clone();
+            parts.push(name.clone());
+            parts.join("::")
+        };
+
+        if !state.config.matches(&full_path) {
+            state.report.filtered_count += 1;
+            continue;
+        }
+
+        state.reporter.start_example(name);
+        let started_at = Instant::now();
+        env.borrow_mut().reset();
 
-            result = match result {
-                Ok(()) => { report.success_count += 1; res },
-                old @ _ => { report.error_count += 1; old }
-            };
+        for before_function in state.befores.iter_mut() {
+            before_function()
+        }
+
+        let res = catch_unwind(AssertUnwindSafe(|| test_function()));
 
-            report.total_tests += 1;
+        for after_function in state.afters.iter_mut().rev() {
+            after_function()
         }
 
+        let res = match res {
+            Ok(res) => res,
+            _ => Err(())
+        };
+
+        state.reporter.end_example(name, res, started_at.elapsed());
+
+        match res {
+            Ok(()) => state.report.success_count += 1,
+            Err(()) => state.report.error_count += 1
+        }
+        if result.is_ok() {
+            result = res;
+        }
+
+        state.report.total_tests += 1;
+    }
+
+    for &mut (ref name, ref mut bench_function) in benches.iter_mut() {
+        let full_path = {
+            let mut parts = state.path.clone();
+            parts.push(name.clone());
+            parts.join("::")
+        };
+
+        if !state.config.matches(&full_path) {
+            continue;
+        }
+
+        env.borrow_mut().reset();
+
+        for before_function in state.befores.iter_mut() {
+            before_function()
+        }
+
+        let mut bencher = Bencher::new();
+        let bench_result = catch_unwind(AssertUnwindSafe(|| bench_function(&mut bencher)));
+
+        for after_function in state.afters.iter_mut().rev() {
+            after_function()
+        }
+
+        if bench_result.is_ok() {
+            if let Some(sample) = bencher.take_sample() {
+                state.report.benches.push(BenchReport {
+                    path: full_path,
+                    ns_per_iter: sample.ns_per_iter,
+                    deviation_ns: sample.deviation_ns
+                });
+            }
+        }
+    }
+
+    for child in children.iter_mut() {
+        result = run_context(child, state, result);
+    }
+
+    for _ in 0..before_count {
+        state.befores.pop();
+    }
+    for _ in 0..after_count {
+        state.afters.pop();
+    }
+
+    state.path.pop();
+    state.reporter.exit_describe();
+
+    result
+}
+
+impl<'a> Runner<'a> {
+
+    pub fn with_config(mut self, config: RunConfig) -> Runner<'a> {
+        self.config = config;
+        self
+    }
+
+    pub fn run(&mut self) -> Result<(), ()> {
+        self.run_with(&mut NullReporter)
+    }
+
+    pub fn run_with(&mut self, reporter: &mut dyn Reporter) -> Result<(), ()> {
+        let mut state = RunState {
+            befores: vec!(),
+            afters: vec!(),
+            path: vec!(),
+            report: TestReport::default(),
+            reporter: reporter,
+            config: &self.config
+        };
+
+        let result = run_context(&mut self.describe, &mut state, Ok(()));
+
         if let Ok(_) = result {
-            self.report = Some(Ok(report))
+            self.report = Some(Ok(state.report))
         } else {
-            self.report = Some(Err(report))
+            self.report = Some(Err(state.report))
         }
 
         Ok(())
     }
 
     pub fn result(&self) -> Result<TestReport, TestReport> {
-        self.report.unwrap_or(Ok(TestReport::default()))
+        self.report.clone().unwrap_or(Ok(TestReport::default()))
     }
 }
 
@@ -288,7 +819,7 @@ mod tests {
                 runner.run().unwrap();
                 let result = runner.result();
 
-                expect!(result).to(be_ok());
+                expect!(result.clone()).to(be_ok());
                 if let Ok(report) = result {
                     expect!(report.total_tests).to(be_equal_to(3));
                 }
@@ -304,7 +835,7 @@ mod tests {
                 runner.run().unwrap();
                 let result = runner.result();
 
-                expect!(result).to(be_ok());
+                expect!(result.clone()).to(be_ok());
                 if let Ok(report) = result {
                     expect!(report.success_count).to(be_equal_to(3));
                 }
@@ -320,11 +851,29 @@ mod tests {
                 runner.run().unwrap();
                 let result = runner.result();
 
-                expect!(result).to(be_err());
+                expect!(result.clone()).to(be_err());
                 if let Err(report) = result {
                     expect!(report.error_count).to(be_equal_to(2));
                 }
             }
+
+            #[test]
+            fn counts_each_test_by_its_own_outcome_not_the_running_aggregate() {
+                let mut runner = describe("a root", |ctx| {
+                    ctx.it("first", || { Ok(()) });
+                    ctx.it("second", || { Err(()) });
+                    ctx.it("third", || { Ok(()) });
+                    ctx.it("fourth", || { Ok(()) });
+                });
+                runner.run().unwrap();
+                let result = runner.result();
+
+                expect!(result.clone()).to(be_err());
+                if let Err(report) = result {
+                    expect!(report.success_count).to(be_equal_to(3));
+                    expect!(report.error_count).to(be_equal_to(1));
+                }
+            }
         }
     }
 
@@ -348,6 +897,370 @@ mod tests {
 
             expect!(ran_counter.load(Ordering::Relaxed)).to(be_equal_to(3));
         }
+
+        #[test]
+        fn runs_in_all_child_contextes() {
+            use std::sync::atomic::{AtomicUsize, Ordering};
+            let outer_counter = &mut AtomicUsize::new(0);
+            let inner_counter = &mut AtomicUsize::new(0);
+
+            {
+                let mut runner = describe("a root", |ctx| {
+                    ctx.before(|| { outer_counter.fetch_add(1, Ordering::Relaxed); });
+                    ctx.describe("nested", |ctx| {
+                        ctx.before(|| { inner_counter.fetch_add(1, Ordering::Relaxed); });
+                        ctx.it("first", || { Ok(()) });
+                        ctx.it("second", || { Ok(()) });
+                    });
+                });
+                runner.run().unwrap();
+            }
+
+            expect!(outer_counter.load(Ordering::Relaxed)).to(be_equal_to(2));
+            expect!(inner_counter.load(Ordering::Relaxed)).to(be_equal_to(2));
+        }
+    }
+
+    mod after {
+        pub use super::*;
+
+        #[test]
+        fn can_be_called_inside_describe() {
+            use std::sync::atomic::{AtomicUsize, Ordering};
+            let ran_counter = &mut AtomicUsize::new(0);
+
+            {
+                let mut runner = describe("a root", |ctx| {
+                    ctx.after(|| { ran_counter.fetch_add(1, Ordering::Relaxed); });
+                    ctx.it("first", || { Ok(()) });
+                    ctx.it("second", || { Ok(()) });
+                    ctx.it("third", || { Ok(()) });
+                });
+                runner.run().unwrap();
+            }
+
+            expect!(ran_counter.load(Ordering::Relaxed)).to(be_equal_to(3));
+        }
+
+        #[test]
+        fn runs_in_all_child_contextes() {
+            use std::sync::atomic::{AtomicUsize, Ordering};
+            let outer_counter = &mut AtomicUsize::new(0);
+            let inner_counter = &mut AtomicUsize::new(0);
+
+            {
+                let mut runner = describe("a root", |ctx| {
+                    ctx.after(|| { outer_counter.fetch_add(1, Ordering::Relaxed); });
+                    ctx.describe("nested", |ctx| {
+                        ctx.after(|| { inner_counter.fetch_add(1, Ordering::Relaxed); });
+                        ctx.it("first", || { Ok(()) });
+                        ctx.it("second", || { Ok(()) });
+                    });
+                });
+                runner.run().unwrap();
+            }
+
+            expect!(outer_counter.load(Ordering::Relaxed)).to(be_equal_to(2));
+            expect!(inner_counter.load(Ordering::Relaxed)).to(be_equal_to(2));
+        }
+
+        #[test]
+        fn runs_even_if_the_test_panics() {
+            use std::sync::atomic::{AtomicUsize, Ordering};
+            let ran_counter = &mut AtomicUsize::new(0);
+
+            {
+                let mut runner = describe("a root", |ctx| {
+                    ctx.after(|| { ran_counter.fetch_add(1, Ordering::Relaxed); });
+                    ctx.it("panics", || { assert_eq!(true, false); Ok(()) });
+                });
+                runner.run().unwrap();
+            }
+
+            expect!(ran_counter.load(Ordering::Relaxed)).to(be_equal_to(1));
+        }
+    }
+
+    mod reporter {
+        pub use super::*;
+
+        #[test]
+        fn json_reporter_records_full_example_paths_and_outcomes() {
+            let mut runner = describe("a root", |ctx| {
+                ctx.describe("nested", |ctx| {
+                    ctx.it("passes", || { Ok(()) });
+                    ctx.it("fails", || { Err(()) });
+                });
+            });
+
+            let mut reporter = JsonReporter::new();
+            runner.run_with(&mut reporter).unwrap();
+
+            let json = reporter.to_json();
+            expect!(json.contains("\"path\":\"a root::nested::passes\",\"outcome\":\"passed\"")).to(be_true());
+            expect!(json.contains("\"path\":\"a root::nested::fails\",\"outcome\":\"failed\"")).to(be_true());
+        }
+
+        #[test]
+        fn documentation_reporter_does_not_disrupt_the_run() {
+            let mut runner = describe("a root", |ctx| {
+                ctx.describe("nested", |ctx| {
+                    ctx.it("passes", || { Ok(()) });
+                });
+            });
+
+            let mut reporter = DocumentationReporter::new();
+            runner.run_with(&mut reporter).unwrap();
+
+            expect!(runner.result()).to(be_ok());
+        }
+
+        #[test]
+        fn json_reporter_escapes_control_characters_in_names() {
+            let mut runner = describe("a root", |ctx| {
+                ctx.it("has a\nnewline\tand a tab", || { Ok(()) });
+            });
+
+            let mut reporter = JsonReporter::new();
+            runner.run_with(&mut reporter).unwrap();
+
+            let json = reporter.to_json();
+            expect!(json.contains("has a\\nnewline\\tand a tab")).to(be_true());
+            expect!(json.contains('\n')).to(be_false());
+            expect!(json.contains('\t')).to(be_false());
+        }
+    }
+
+    mod run_config {
+        pub use super::*;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        #[test]
+        fn only_runs_examples_whose_path_contains_the_filter() {
+            let ran_counter = &mut AtomicUsize::new(0);
+
+            {
+                let mut runner = describe("a root", |ctx| {
+                    ctx.it("first run", || { ran_counter.fetch_add(1, Ordering::Relaxed); Ok(()) });
+                    ctx.it("second run", || { ran_counter.fetch_add(1, Ordering::Relaxed); Ok(()) });
+                }).with_config(RunConfig::new().filter("second"));
+
+                runner.run().unwrap();
+
+                let report = runner.result().unwrap();
+                expect!(report.total_tests).to(be_equal_to(1));
+                expect!(report.filtered_count).to(be_equal_to(1));
+            }
+
+            expect!(ran_counter.load(Ordering::Relaxed)).to(be_equal_to(1));
+        }
+
+        #[test]
+        fn exact_flag_requires_a_full_path_match() {
+            let mut runner = describe("a root", |ctx| {
+                ctx.it("first run", || { Ok(()) });
+            }).with_config(RunConfig::new().filter("first").exact(true));
+
+            runner.run().unwrap();
+
+            let report = runner.result().unwrap();
+            expect!(report.total_tests).to(be_equal_to(0));
+            expect!(report.filtered_count).to(be_equal_to(1));
+        }
+
+        #[test]
+        fn invert_flag_skips_matching_examples() {
+            let mut runner = describe("a root", |ctx| {
+                ctx.it("first run", || { Ok(()) });
+                ctx.it("second run", || { Ok(()) });
+            }).with_config(RunConfig::new().filter("first").invert(true));
+
+            runner.run().unwrap();
+
+            let report = runner.result().unwrap();
+            expect!(report.total_tests).to(be_equal_to(1));
+            expect!(report.filtered_count).to(be_equal_to(1));
+        }
+    }
+
+    mod bench {
+        pub use super::*;
+
+        #[test]
+        fn records_a_bench_report_with_the_full_path() {
+            let mut runner = describe("a root", |ctx| {
+                ctx.describe("nested", |ctx| {
+                    ctx.bench("adds numbers", |b| {
+                        b.iter(|| black_box(1) + black_box(1));
+                    });
+                });
+            });
+
+            runner.run().unwrap();
+
+            let report = runner.result().unwrap();
+            expect!(report.benches.len()).to(be_equal_to(1));
+            expect!(report.benches[0].path.as_str()).to(be_equal_to("a root::nested::adds numbers"));
+        }
+
+        #[test]
+        fn black_box_returns_its_argument() {
+            expect!(black_box(42)).to(be_equal_to(42));
+        }
+    }
+
+    mod it_each {
+        pub use super::*;
+
+        #[test]
+        fn generates_one_example_per_value() {
+            let mut runner = describe("a root", |ctx| {
+                ctx.it_each("is odd", vec![1, 3, 5], |n| {
+                    if n % 2 == 1 { Ok(()) } else { Err(()) }
+                });
+            });
+
+            runner.run().unwrap();
+
+            let report = runner.result().unwrap();
+            expect!(report.total_tests).to(be_equal_to(3));
+            expect!(report.success_count).to(be_equal_to(3));
+            expect!(report.error_count).to(be_equal_to(0));
+        }
+
+        #[test]
+        fn names_each_case_from_the_index_and_sanitized_value() {
+            let mut runner = describe("a root", |ctx| {
+                ctx.it_each("takes", vec!["a b", "c"], |_| Ok(()));
+            });
+
+            let mut reporter = JsonReporter::new();
+            runner.run_with(&mut reporter).unwrap();
+
+            let json = reporter.to_json();
+            expect!(json.contains("\"path\":\"a root::takes_0__a_b_\"")).to(be_true());
+            expect!(json.contains("\"path\":\"a root::takes_1__c_\"")).to(be_true());
+        }
+
+        #[test]
+        fn a_failing_case_does_not_abort_the_others() {
+            let mut runner = describe("a root", |ctx| {
+                ctx.it_each("is odd", vec![1, 2, 3], |n| {
+                    if n % 2 == 1 { Ok(()) } else { Err(()) }
+                });
+            });
+            runner.run().unwrap();
+            let result = runner.result();
+
+            expect!(result.clone()).to(be_err());
+            if let Err(report) = result {
+                expect!(report.total_tests).to(be_equal_to(3));
+                expect!(report.success_count).to(be_equal_to(2));
+                expect!(report.error_count).to(be_equal_to(1));
+            }
+        }
+
+        #[test]
+        fn can_be_run_more_than_once() {
+            let mut runner = describe("a root", |ctx| {
+                ctx.it_each("is odd", vec![1, 3, 5], |n| {
+                    if n % 2 == 1 { Ok(()) } else { Err(()) }
+                });
+            });
+
+            runner.run().unwrap();
+            runner.run().unwrap();
+
+            let report = runner.result().unwrap();
+            expect!(report.total_tests).to(be_equal_to(3));
+            expect!(report.success_count).to(be_equal_to(3));
+            expect!(report.error_count).to(be_equal_to(0));
+        }
+    }
+
+    mod it_matrix {
+        pub use super::*;
+
+        #[test]
+        fn generates_the_cartesian_product_of_both_value_lists() {
+            let mut runner = describe("a root", |ctx| {
+                ctx.it_matrix("sums to even", vec![1, 2], vec![10, 20], |a, b| {
+                    if (a + b) % 2 == 0 { Ok(()) } else { Err(()) }
+                });
+            });
+
+            runner.run().unwrap();
+            let result = runner.result();
+
+            expect!(result.clone()).to(be_err());
+            if let Err(report) = result {
+                expect!(report.total_tests).to(be_equal_to(4));
+                expect!(report.success_count + report.error_count).to(be_equal_to(4));
+            }
+        }
+    }
+
+    mod set {
+        pub use super::*;
+
+        #[test]
+        fn is_shared_between_a_before_hook_and_the_test_body() {
+            let mut runner = describe("a root", |ctx| {
+                let value = ctx.set(|| 41);
+                let in_before = value.clone();
+                ctx.before(move || { assert_eq!(*in_before.get(), 41); });
+                ctx.it("sees the same value", move || {
+                    if *value.get() == 41 { Ok(()) } else { Err(()) }
+                });
+            });
+
+            runner.run().unwrap();
+
+            expect!(runner.result()).to(be_ok());
+        }
+
+        #[test]
+        fn only_evaluates_the_initializer_once_per_example() {
+            let init_count = Rc::new(RefCell::new(0));
+
+            {
+                let counted = init_count.clone();
+                let mut runner = describe("a root", |ctx| {
+                    let counted = counted.clone();
+                    let value = ctx.set(move || { *counted.borrow_mut() += 1; 7 });
+                    let first = value.clone();
+                    let second = value.clone();
+                    ctx.it("reads it twice", move || {
+                        let a = *first.get();
+                        let b = *second.get();
+                        if a == 7 && b == 7 { Ok(()) } else { Err(()) }
+                    });
+                });
+                runner.run().unwrap();
+            }
+
+            expect!(*init_count.borrow()).to(be_equal_to(1));
+        }
+
+        #[test]
+        fn re_evaluates_the_initializer_for_each_example() {
+            let init_count = Rc::new(RefCell::new(0));
+
+            {
+                let counted = init_count.clone();
+                let mut runner = describe("a root", |ctx| {
+                    let counted = counted.clone();
+                    let value = ctx.set(move || { *counted.borrow_mut() += 1; 7 });
+                    let first = value.clone();
+                    let second = value.clone();
+                    ctx.it("first", move || { first.get(); Ok(()) });
+                    ctx.it("second", move || { second.get(); Ok(()) });
+                });
+                runner.run().unwrap();
+            }
+
+            expect!(*init_count.borrow()).to(be_equal_to(2));
+        }
     }
 
     /*
@@ -357,8 +1270,8 @@ mod tests {
      * x runner can count the tests
      * x runner can count the success and failures
      * - check that runner displays the tests names and their results
-     * - check that we can use before in a describe
-     * - check that we can use after in a describe
-     * - check that after/before are run in all child contextes
+     * x check that we can use before in a describe
+     * x check that we can use after in a describe
+     * x check that after/before are run in all child contextes
      */
 }